@@ -0,0 +1,248 @@
+// Thin client for the AT Protocol XRPC endpoints used by this app.
+use serde::{Deserialize, Serialize};
+
+use crate::media;
+use crate::session::Session;
+use crate::{Author, Post};
+
+const EXPIRED_TOKEN: &str = "ExpiredToken";
+
+#[derive(Serialize)]
+struct CreateSessionRequest<'a> {
+    identifier: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize)]
+struct CreateSessionResponse {
+    did: String,
+    handle: String,
+    #[serde(rename = "accessJwt")]
+    access_jwt: String,
+    #[serde(rename = "refreshJwt")]
+    refresh_jwt: String,
+}
+
+#[derive(Deserialize)]
+struct RefreshSessionResponse {
+    did: String,
+    handle: String,
+    #[serde(rename = "accessJwt")]
+    access_jwt: String,
+    #[serde(rename = "refreshJwt")]
+    refresh_jwt: String,
+}
+
+#[derive(Deserialize)]
+struct GetTimelineResponse {
+    feed: Vec<FeedViewPost>,
+}
+
+#[derive(Deserialize)]
+struct FeedViewPost {
+    post: PostView,
+}
+
+#[derive(Deserialize)]
+struct PostView {
+    uri: String,
+    author: AuthorView,
+    record: PostRecord,
+    embed: Option<serde_json::Value>,
+    #[serde(rename = "likeCount", default)]
+    like_count: i32,
+    #[serde(rename = "repostCount", default)]
+    repost_count: i32,
+    #[serde(rename = "replyCount", default)]
+    reply_count: i32,
+}
+
+#[derive(Deserialize)]
+struct AuthorView {
+    did: String,
+    handle: String,
+    #[serde(rename = "displayName", default)]
+    display_name: String,
+    avatar: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PostRecord {
+    text: String,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+}
+
+#[derive(Deserialize)]
+struct XrpcError {
+    error: String,
+}
+
+/// Maps a non-2xx XRPC response to either `ExpiredToken` or a generic message,
+/// so callers can decide whether to retry after a `refreshSession`.
+fn xrpc_error(status: reqwest::StatusCode, body: &str) -> String {
+    if status == reqwest::StatusCode::BAD_REQUEST {
+        if let Ok(err) = serde_json::from_str::<XrpcError>(body) {
+            if err.error == EXPIRED_TOKEN {
+                return EXPIRED_TOKEN.to_string();
+            }
+        }
+    }
+    format!("xrpc request failed: {status}")
+}
+
+/// Returns true when `error` is the sentinel produced by [`xrpc_error`] for an
+/// expired access token, as returned by any XRPC endpoint with HTTP 400.
+pub fn is_expired_token(error: &str) -> bool {
+    error == EXPIRED_TOKEN
+}
+
+/// Calls `com.atproto.server.createSession` and returns the resulting `Session`.
+pub async fn create_session(service: &str, identifier: &str, password: &str) -> Result<Session, String> {
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{service}/xrpc/com.atproto.server.createSession"))
+        .json(&CreateSessionRequest { identifier, password })
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = resp.status();
+    let body = resp.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(xrpc_error(status, &body));
+    }
+
+    let parsed: CreateSessionResponse = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+    Ok(Session {
+        did: parsed.did,
+        handle: parsed.handle,
+        service: service.to_string(),
+        access_jwt: parsed.access_jwt,
+        refresh_jwt: parsed.refresh_jwt,
+    })
+}
+
+/// Calls `com.atproto.server.refreshSession` using the session's refresh token.
+pub async fn refresh_session(service: &str, session: &Session) -> Result<Session, String> {
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{service}/xrpc/com.atproto.server.refreshSession"))
+        .bearer_auth(&session.refresh_jwt)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = resp.status();
+    let body = resp.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(xrpc_error(status, &body));
+    }
+
+    let parsed: RefreshSessionResponse = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+    Ok(Session {
+        did: parsed.did,
+        handle: parsed.handle,
+        service: service.to_string(),
+        access_jwt: parsed.access_jwt,
+        refresh_jwt: parsed.refresh_jwt,
+    })
+}
+
+/// Extracts the CDN `fullsize` URLs from a post's `app.bsky.embed.images#view`
+/// embed and rewrites each to an `atblob://{did}/{cid}` reference via
+/// [`media::blob_uri`].
+fn embed_image_uris(did: &str, embed: &Option<serde_json::Value>) -> Option<Vec<String>> {
+    let images = embed.as_ref()?.get("images")?.as_array()?;
+    let uris: Vec<String> = images
+        .iter()
+        .filter_map(|image| image.get("fullsize")?.as_str())
+        .filter_map(|fullsize| media::blob_uri(did, fullsize))
+        .collect();
+
+    if uris.is_empty() {
+        None
+    } else {
+        Some(uris)
+    }
+}
+
+/// Calls `app.bsky.feed.getTimeline` with the given access token.
+pub async fn get_timeline(service: &str, access_jwt: &str) -> Result<Vec<Post>, String> {
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!("{service}/xrpc/app.bsky.feed.getTimeline"))
+        .bearer_auth(access_jwt)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = resp.status();
+    let body = resp.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(xrpc_error(status, &body));
+    }
+
+    let parsed: GetTimelineResponse = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+    Ok(parsed
+        .feed
+        .into_iter()
+        .map(|item| {
+            let did = item.post.author.did;
+            let images = embed_image_uris(&did, &item.post.embed);
+            let avatar = item
+                .post
+                .author
+                .avatar
+                .as_deref()
+                .and_then(|url| media::blob_uri(&did, url));
+            Post {
+                id: item.post.uri,
+                author: Author {
+                    did,
+                    handle: item.post.author.handle,
+                    display_name: item.post.author.display_name,
+                    avatar,
+                },
+                text: item.post.record.text,
+                created_at: item.post.record.created_at,
+                images,
+                likes_count: item.post.like_count,
+                reposts_count: item.post.repost_count,
+                replies_count: item.post.reply_count,
+            }
+        })
+        .collect())
+}
+
+pub async fn create_post(_service: &str, _access_jwt: &str, _text: &str) -> Result<String, String> {
+    Ok("".to_string())
+}
+
+pub async fn like_post(_service: &str, _access_jwt: &str, _post_uri: &str) -> Result<bool, String> {
+    Ok(true)
+}
+
+pub async fn get_post_detail(_service: &str, _access_jwt: Option<&str>, _post_uri: &str) -> Result<Post, String> {
+    Ok(Post {
+        id: "".to_string(),
+        author: Author {
+            did: "".to_string(),
+            handle: "".to_string(),
+            display_name: "".to_string(),
+            avatar: None,
+        },
+        text: "".to_string(),
+        created_at: "".to_string(),
+        images: None,
+        likes_count: 0,
+        reposts_count: 0,
+        replies_count: 0,
+    })
+}
+
+pub async fn get_post_replies(_service: &str, _access_jwt: Option<&str>, _post_uri: &str) -> Result<Vec<Post>, String> {
+    Ok(Vec::new())
+}
+
+pub fn main() {}
@@ -0,0 +1,251 @@
+// Offline cache for timeline and post data, backed by a local SQLite file.
+mod models;
+mod schema;
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+use crate::Post;
+use models::DbPost;
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+pub struct DbConnection(pub Mutex<SqliteConnection>);
+
+/// Opens (creating if needed) `cache.sqlite` under the app data dir and runs
+/// any pending migrations.
+pub fn establish_connection(app_data_dir: &Path) -> DbConnection {
+    std::fs::create_dir_all(app_data_dir).expect("failed to create app data dir");
+    let db_path = app_data_dir.join("cache.sqlite");
+    let mut conn = SqliteConnection::establish(&db_path.to_string_lossy())
+        .expect("failed to open offline cache database");
+    conn.run_pending_migrations(MIGRATIONS)
+        .expect("failed to run cache migrations");
+    DbConnection(Mutex::new(conn))
+}
+
+/// Writes `post` and its author into the cache, overwriting any existing rows.
+pub fn cache_post(conn: &mut SqliteConnection, post: &Post, cached_at: &str) -> QueryResult<()> {
+    use schema::authors;
+    use schema::posts;
+
+    let db_author = models::DbAuthor::from(&post.author);
+    diesel::insert_into(authors::table)
+        .values(&db_author)
+        .on_conflict(authors::did)
+        .do_update()
+        .set(&db_author)
+        .execute(conn)?;
+
+    let db_post = DbPost::from_post(post, cached_at);
+    diesel::insert_into(posts::table)
+        .values(&db_post)
+        .on_conflict(posts::id)
+        .do_update()
+        .set(&db_post)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Writes a batch of timeline posts, recording their feed position so
+/// `cached_timeline` can preserve the original order, and bumps
+/// `timeline_cursor.last_synced_at`.
+pub fn cache_timeline(conn: &mut SqliteConnection, feed: &[Post], cached_at: &str) -> QueryResult<()> {
+    use schema::{timeline_cursor, timeline_feed};
+
+    for post in feed {
+        cache_post(conn, post, cached_at)?;
+    }
+
+    diesel::delete(timeline_feed::table).execute(conn)?;
+    for (position, post) in feed.iter().enumerate() {
+        diesel::insert_into(timeline_feed::table)
+            .values((
+                timeline_feed::position.eq(position as i32),
+                timeline_feed::post_id.eq(&post.id),
+            ))
+            .execute(conn)?;
+    }
+
+    diesel::insert_into(timeline_cursor::table)
+        .values((
+            timeline_cursor::id.eq(0),
+            timeline_cursor::last_synced_at.eq(cached_at),
+        ))
+        .on_conflict(timeline_cursor::id)
+        .do_update()
+        .set(timeline_cursor::last_synced_at.eq(cached_at))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Returns the most recently cached timeline posts, in their original feed
+/// order.
+pub fn cached_timeline(conn: &mut SqliteConnection, limit: i64) -> QueryResult<Vec<Post>> {
+    use schema::authors;
+    use schema::posts;
+    use schema::timeline_feed;
+
+    let rows: Vec<(DbPost, models::DbAuthor)> = timeline_feed::table
+        .inner_join(posts::table.on(posts::id.eq(timeline_feed::post_id)))
+        .inner_join(authors::table.on(posts::author_did.eq(authors::did)))
+        .order(timeline_feed::position.asc())
+        .limit(limit)
+        .select((posts::all_columns, authors::all_columns))
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(db_post, db_author)| db_post.into_post(db_author.into()))
+        .collect())
+}
+
+/// Returns a single cached post by its AT-URI, if present.
+pub fn cached_post(conn: &mut SqliteConnection, post_id: &str) -> QueryResult<Option<Post>> {
+    use schema::authors;
+    use schema::posts;
+
+    posts::table
+        .inner_join(authors::table.on(posts::author_did.eq(authors::did)))
+        .filter(posts::id.eq(post_id))
+        .first::<(DbPost, models::DbAuthor)>(conn)
+        .optional()
+        .map(|row| row.map(|(db_post, db_author)| db_post.into_post(db_author.into())))
+}
+
+/// Writes the replies to `parent_id`, recording their order.
+pub fn cache_replies(
+    conn: &mut SqliteConnection,
+    parent_id: &str,
+    replies: &[Post],
+    cached_at: &str,
+) -> QueryResult<()> {
+    use schema::post_replies;
+
+    for (position, reply) in replies.iter().enumerate() {
+        cache_post(conn, reply, cached_at)?;
+        diesel::insert_into(post_replies::table)
+            .values((
+                post_replies::parent_id.eq(parent_id),
+                post_replies::reply_id.eq(&reply.id),
+                post_replies::position.eq(position as i32),
+            ))
+            .on_conflict((post_replies::parent_id, post_replies::reply_id))
+            .do_update()
+            .set(post_replies::position.eq(position as i32))
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the cached replies to `parent_id`, in their original order.
+pub fn cached_replies(conn: &mut SqliteConnection, parent_id: &str) -> QueryResult<Vec<Post>> {
+    use schema::authors;
+    use schema::post_replies;
+    use schema::posts;
+
+    let rows: Vec<(DbPost, models::DbAuthor)> = post_replies::table
+        .inner_join(posts::table.on(posts::id.eq(post_replies::reply_id)))
+        .inner_join(authors::table.on(posts::author_did.eq(authors::did)))
+        .filter(post_replies::parent_id.eq(parent_id))
+        .order(post_replies::position.asc())
+        .select((posts::all_columns, authors::all_columns))
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(db_post, db_author)| db_post.into_post(db_author.into()))
+        .collect())
+}
+
+/// Returns the cached profile for `did`, if one has been seen via a cached
+/// post's author.
+pub fn cached_author(conn: &mut SqliteConnection, did: &str) -> QueryResult<Option<crate::Author>> {
+    use schema::authors;
+
+    authors::table
+        .filter(authors::did.eq(did))
+        .first::<models::DbAuthor>(conn)
+        .optional()
+        .map(|row| row.map(Into::into))
+}
+
+/// Records that `account_did` liked `post_uri`, for the `home_info` "total
+/// likes given" stat. `SessionStore` holds multiple accounts, so likes are
+/// scoped per-DID rather than globally.
+pub fn record_like(
+    conn: &mut SqliteConnection,
+    account_did: &str,
+    post_uri: &str,
+    liked_at: &str,
+) -> QueryResult<()> {
+    use schema::likes_given;
+
+    diesel::insert_into(likes_given::table)
+        .values((
+            likes_given::account_did.eq(account_did),
+            likes_given::post_uri.eq(post_uri),
+            likes_given::liked_at.eq(liked_at),
+        ))
+        .on_conflict((likes_given::account_did, likes_given::post_uri))
+        .do_nothing()
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Total number of posts `account_did` has liked, per `likes_given`.
+pub fn count_likes_given(conn: &mut SqliteConnection, account_did: &str) -> QueryResult<i64> {
+    use schema::likes_given;
+
+    likes_given::table
+        .filter(likes_given::account_did.eq(account_did))
+        .count()
+        .get_result(conn)
+}
+
+/// Increments `posts.likes_count` for `post_id` and returns the new total, or
+/// `1` if the post isn't cached yet (the firehose can observe a like before
+/// the post itself has been synced).
+pub fn increment_like_count(conn: &mut SqliteConnection, post_id: &str) -> QueryResult<i32> {
+    use schema::posts;
+
+    let updated = diesel::update(posts::table.filter(posts::id.eq(post_id)))
+        .set(posts::likes_count.eq(posts::likes_count + 1))
+        .execute(conn)?;
+
+    if updated == 0 {
+        return Ok(1);
+    }
+
+    posts::table
+        .filter(posts::id.eq(post_id))
+        .select(posts::likes_count)
+        .first(conn)
+}
+
+/// Total number of posts currently held in the cache.
+pub fn cached_post_count(conn: &mut SqliteConnection) -> QueryResult<i64> {
+    use schema::posts;
+
+    posts::table.count().get_result(conn)
+}
+
+/// Timestamp of the last successful `get_timeline` sync, if any.
+pub fn last_synced_at(conn: &mut SqliteConnection) -> QueryResult<Option<String>> {
+    use schema::timeline_cursor;
+
+    timeline_cursor::table
+        .select(timeline_cursor::last_synced_at)
+        .filter(timeline_cursor::id.eq(0))
+        .first(conn)
+        .optional()
+        .map(|row: Option<Option<String>>| row.flatten())
+}
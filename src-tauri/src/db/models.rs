@@ -0,0 +1,86 @@
+use diesel::prelude::*;
+
+use super::schema::{authors, posts};
+use crate::{Author, Post};
+
+#[derive(Queryable, Insertable, AsChangeset)]
+#[diesel(table_name = authors)]
+#[diesel(primary_key(did))]
+pub struct DbAuthor {
+    pub did: String,
+    pub handle: String,
+    pub display_name: String,
+    pub avatar: Option<String>,
+}
+
+impl From<&Author> for DbAuthor {
+    fn from(author: &Author) -> Self {
+        DbAuthor {
+            did: author.did.clone(),
+            handle: author.handle.clone(),
+            display_name: author.display_name.clone(),
+            avatar: author.avatar.clone(),
+        }
+    }
+}
+
+impl From<DbAuthor> for Author {
+    fn from(db: DbAuthor) -> Self {
+        Author {
+            did: db.did,
+            handle: db.handle,
+            display_name: db.display_name,
+            avatar: db.avatar,
+        }
+    }
+}
+
+#[derive(Queryable, Insertable, AsChangeset)]
+#[diesel(table_name = posts)]
+#[diesel(primary_key(id))]
+pub struct DbPost {
+    pub id: String,
+    pub author_did: String,
+    pub text: String,
+    pub created_at: String,
+    pub images: Option<String>,
+    pub likes_count: i32,
+    pub reposts_count: i32,
+    pub replies_count: i32,
+    pub cached_at: String,
+}
+
+impl DbPost {
+    pub fn from_post(post: &Post, cached_at: &str) -> Self {
+        DbPost {
+            id: post.id.clone(),
+            author_did: post.author.did.clone(),
+            text: post.text.clone(),
+            created_at: post.created_at.clone(),
+            images: post
+                .images
+                .as_ref()
+                .map(|images| serde_json::to_string(images).unwrap_or_default()),
+            likes_count: post.likes_count,
+            reposts_count: post.reposts_count,
+            replies_count: post.replies_count,
+            cached_at: cached_at.to_string(),
+        }
+    }
+
+    pub fn into_post(self, author: Author) -> Post {
+        Post {
+            id: self.id,
+            author,
+            text: self.text,
+            created_at: self.created_at,
+            images: self
+                .images
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok()),
+            likes_count: self.likes_count,
+            reposts_count: self.reposts_count,
+            replies_count: self.replies_count,
+        }
+    }
+}
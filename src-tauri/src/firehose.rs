@@ -0,0 +1,160 @@
+// Background subscription to the Jetstream firehose, re-emitted as Tauri
+// events so the frontend can react to new posts/likes instead of polling.
+use std::sync::Mutex;
+
+use futures_util::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::db::{self, DbConnection};
+use crate::media;
+use crate::{Author, Post};
+
+const JETSTREAM_URL: &str =
+    "wss://jetstream2.us-east.bsky.network/subscribe?wantedCollections=app.bsky.feed.post&wantedCollections=app.bsky.feed.like";
+
+#[derive(Default)]
+pub struct FirehoseHandle(pub Mutex<Option<JoinHandle<()>>>);
+
+#[derive(Deserialize)]
+struct JetstreamEvent {
+    did: String,
+    commit: Option<JetstreamCommit>,
+}
+
+#[derive(Deserialize)]
+struct JetstreamCommit {
+    collection: String,
+    rkey: String,
+    operation: String,
+    record: Option<Value>,
+}
+
+/// Starts the firehose subscription as a background task, storing its
+/// `JoinHandle` in `handle` so it can later be aborted by `stop_firehose`.
+#[tauri::command]
+#[specta::specta]
+pub fn start_firehose(app: AppHandle, handle: tauri::State<FirehoseHandle>) -> Result<(), String> {
+    let mut guard = handle.0.lock().unwrap();
+    if guard.is_some() {
+        return Ok(());
+    }
+
+    let app = app.clone();
+    *guard = Some(tauri::async_runtime::spawn(async move {
+        if let Err(e) = run(app).await {
+            log::error!("firehose subscription ended: {e}");
+        }
+    }));
+
+    Ok(())
+}
+
+/// Aborts the background firehose task, if one is running.
+#[tauri::command]
+#[specta::specta]
+pub fn stop_firehose(handle: tauri::State<FirehoseHandle>) {
+    if let Some(task) = handle.0.lock().unwrap().take() {
+        task.abort();
+    }
+}
+
+async fn run(app: AppHandle) -> Result<(), String> {
+    let (ws, _) = tokio_tungstenite::connect_async(JETSTREAM_URL)
+        .await
+        .map_err(|e| e.to_string())?;
+    let (_, mut read) = ws.split();
+
+    while let Some(message) = read.next().await {
+        let message = message.map_err(|e| e.to_string())?;
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let Ok(event) = serde_json::from_str::<JetstreamEvent>(&text) else {
+            continue;
+        };
+        handle_event(&app, event);
+    }
+
+    Ok(())
+}
+
+fn handle_event(app: &AppHandle, event: JetstreamEvent) {
+    let Some(commit) = event.commit else { return };
+    if commit.operation != "create" {
+        return;
+    }
+
+    match commit.collection.as_str() {
+        "app.bsky.feed.post" => {
+            let uri = format!("at://{}/{}/{}", event.did, commit.collection, commit.rkey);
+            if let Some(post) = parse_post(&event.did, &uri, commit.record) {
+                let _ = app.emit("post:new", post);
+            }
+        }
+        "app.bsky.feed.like" => {
+            if let Some(subject_uri) = commit
+                .record
+                .as_ref()
+                .and_then(|record| record.get("subject"))
+                .and_then(|subject| subject.get("uri"))
+                .and_then(|uri| uri.as_str())
+            {
+                let count = bump_like_count(app, subject_uri);
+                let _ = app.emit(
+                    "post:like",
+                    serde_json::json!({ "uri": subject_uri, "count": count }),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Increments the cached like count for `post_uri` and returns the new total,
+/// so `post:like` events carry a running count instead of just the URI.
+fn bump_like_count(app: &AppHandle, post_uri: &str) -> i32 {
+    let db = app.state::<DbConnection>();
+    let mut conn = db.0.lock().unwrap();
+    db::increment_like_count(&mut conn, post_uri).unwrap_or(1)
+}
+
+fn parse_post(did: &str, uri: &str, record: Option<Value>) -> Option<Post> {
+    let record = record?;
+    Some(Post {
+        id: uri.to_string(),
+        author: Author {
+            did: did.to_string(),
+            handle: String::new(),
+            display_name: String::new(),
+            avatar: None,
+        },
+        text: record.get("text")?.as_str()?.to_string(),
+        created_at: record.get("createdAt")?.as_str()?.to_string(),
+        images: embed_image_uris(did, &record),
+        likes_count: 0,
+        reposts_count: 0,
+        replies_count: 0,
+    })
+}
+
+/// Extracts blob CIDs from a raw `app.bsky.feed.post` record's
+/// `embed.images[].image.ref.$link` and rewrites each to an
+/// `atblob://{did}/{cid}` reference via [`media::blob_uri`].
+fn embed_image_uris(did: &str, record: &Value) -> Option<Vec<String>> {
+    let images = record.get("embed")?.get("images")?.as_array()?;
+    let uris: Vec<String> = images
+        .iter()
+        .filter_map(|image| image.get("image")?.get("ref")?.get("$link")?.as_str())
+        .filter_map(|cid| media::blob_uri(did, cid))
+        .collect();
+
+    if uris.is_empty() {
+        None
+    } else {
+        Some(uris)
+    }
+}
@@ -1,8 +1,18 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
 mod bluesky;
+mod db;
+mod firehose;
+mod media;
+mod session;
+
+use db::DbConnection;
+use firehose::FirehoseHandle;
+use session::{Session, SessionStore};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, specta::Type)]
 pub struct Post {
     id: String,
     author: Author,
@@ -14,7 +24,7 @@ pub struct Post {
     replies_count: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, specta::Type)]
 pub struct Author {
     did: String,
     handle: String,
@@ -22,69 +32,254 @@ pub struct Author {
     avatar: Option<String>,
 }
 
+/// Looks up the session for `did` and runs `call` against it. If `call` fails
+/// with an expired access token, refreshes the session once via
+/// `com.atproto.server.refreshSession`, stores the new tokens, and retries.
+async fn with_session<T, F, Fut>(service: &str, store: &SessionStore, did: &str, call: F) -> Result<T, String>
+where
+    F: Fn(Session) -> Fut,
+    Fut: std::future::Future<Output = Result<T, String>>,
+{
+    let session = store.get(did)?;
+    match call(session.clone()).await {
+        Err(e) if bluesky::is_expired_token(&e) => {
+            let refreshed = bluesky::refresh_session(service, &session).await?;
+            store.update(refreshed.clone());
+            call(refreshed).await
+        }
+        result => result,
+    }
+}
+
+/// Fetches the timeline over the network, caching the result. If the network
+/// call fails, falls back to the most recently cached posts.
 #[tauri::command]
-fn get_timeline(service: &str, session: Option<&str>) -> Result<Vec<Post>, String> {
-    bluesky::get_timeline();
-    return Ok(Vec::new());
+#[specta::specta]
+async fn get_timeline(
+    service: String,
+    did: String,
+    state: tauri::State<'_, SessionStore>,
+    db: tauri::State<'_, DbConnection>,
+) -> Result<Vec<Post>, String> {
+    let fetched = with_session(&service, &state, &did, |session| {
+        let service = service.clone();
+        async move { bluesky::get_timeline(&service, &session.access_jwt).await }
+    })
+    .await;
+
+    let mut conn = db.0.lock().unwrap();
+    match fetched {
+        Ok(posts) => {
+            let cached_at = chrono::Utc::now().to_rfc3339();
+            let _ = db::cache_timeline(&mut conn, &posts, &cached_at);
+            Ok(posts)
+        }
+        Err(e) => db::cached_timeline(&mut conn, 50).map_err(|_| e),
+    }
 }
 
 #[tauri::command]
-fn login(service: &str, identifier: &str, password: &str) -> Result<String, String> {
-    bluesky::login();
-    return Ok("".to_string());
+#[specta::specta]
+async fn login(
+    service: String,
+    identifier: String,
+    password: String,
+    state: tauri::State<'_, SessionStore>,
+) -> Result<String, String> {
+    let session = bluesky::create_session(&service, &identifier, &password).await?;
+    let did = session.did.clone();
+    state.insert(session);
+    return Ok(did);
 }
 
 #[tauri::command]
-async fn create_post(service: &str, session: &str, text: &str) -> Result<String, String> {
-    bluesky::create_post();
-    return Ok("".to_string());
+#[specta::specta]
+async fn create_post(
+    service: String,
+    did: String,
+    text: String,
+    state: tauri::State<'_, SessionStore>,
+) -> Result<String, String> {
+    with_session(&service, &state, &did, |session| {
+        let service = service.clone();
+        let text = text.clone();
+        async move { bluesky::create_post(&service, &session.access_jwt, &text).await }
+    })
+    .await
 }
 
 #[tauri::command]
-async fn like_post(service: &str, session: &str, post_uri: &str) -> Result<bool, String> {
-    bluesky::like_post();
-    return Ok(true);
+#[specta::specta]
+async fn like_post(
+    service: String,
+    did: String,
+    post_uri: String,
+    state: tauri::State<'_, SessionStore>,
+    db: tauri::State<'_, DbConnection>,
+) -> Result<bool, String> {
+    let liked = with_session(&service, &state, &did, |session| {
+        let service = service.clone();
+        let post_uri = post_uri.clone();
+        async move { bluesky::like_post(&service, &session.access_jwt, &post_uri).await }
+    })
+    .await?;
+
+    if liked {
+        let liked_at = chrono::Utc::now().to_rfc3339();
+        let mut conn = db.0.lock().unwrap();
+        let _ = db::record_like(&mut conn, &did, &post_uri, &liked_at);
+    }
+
+    Ok(liked)
 }
 
+/// Fetches a post over the network, caching it. Falls back to the cached
+/// copy if the network call fails.
 #[tauri::command]
-async fn get_post_detail(service: &str, session: Option<&str>, post_uri: &str) -> Result<Post, String> {
-    bluesky::get_post_detail();
-    return Ok(Post {
-        id: "".to_string(),
-        author: Author {
-            did: "".to_string(),
-            handle: "".to_string(),
-            display_name: "".to_string(),
-            avatar: None
-        },
-        text: "".to_string(),
-        created_at: "".to_string(),
-        images: None,
-        likes_count: 0,
-        reposts_count: 0,
-        replies_count: 0
-    });
+#[specta::specta]
+async fn get_post_detail(
+    service: String,
+    did: Option<String>,
+    post_uri: String,
+    state: tauri::State<'_, SessionStore>,
+    db: tauri::State<'_, DbConnection>,
+) -> Result<Post, String> {
+    let fetched = match &did {
+        Some(did) => {
+            with_session(&service, &state, did, |session| {
+                let service = service.clone();
+                let post_uri = post_uri.clone();
+                async move { bluesky::get_post_detail(&service, Some(&session.access_jwt), &post_uri).await }
+            })
+            .await
+        }
+        None => bluesky::get_post_detail(&service, None, &post_uri).await,
+    };
+
+    let mut conn = db.0.lock().unwrap();
+    match fetched {
+        // `bluesky::get_post_detail` is still a stub that returns a blank
+        // post; skip the write-through so it doesn't seed junk cache rows.
+        Ok(post) if post.id.is_empty() => Ok(post),
+        Ok(post) => {
+            let cached_at = chrono::Utc::now().to_rfc3339();
+            let _ = db::cache_post(&mut conn, &post, &cached_at);
+            Ok(post)
+        }
+        Err(e) => db::cached_post(&mut conn, &post_uri)
+            .map_err(|_| e.clone())?
+            .ok_or(e),
+    }
 }
 
+/// Fetches a post's replies over the network, caching them. Falls back to
+/// the cached replies if the network call fails.
 #[tauri::command]
-async fn get_post_replies(service: &str, session: Option<&str>, post_uri: &str) -> Result<Vec<Post>, String> {
-    bluesky::get_post_replies();
-    return Ok(Vec::new());
+#[specta::specta]
+async fn get_post_replies(
+    service: String,
+    did: Option<String>,
+    post_uri: String,
+    state: tauri::State<'_, SessionStore>,
+    db: tauri::State<'_, DbConnection>,
+) -> Result<Vec<Post>, String> {
+    let fetched = match &did {
+        Some(did) => {
+            with_session(&service, &state, did, |session| {
+                let service = service.clone();
+                let post_uri = post_uri.clone();
+                async move { bluesky::get_post_replies(&service, Some(&session.access_jwt), &post_uri).await }
+            })
+            .await
+        }
+        None => bluesky::get_post_replies(&service, None, &post_uri).await,
+    };
+
+    let mut conn = db.0.lock().unwrap();
+    match fetched {
+        Ok(replies) => {
+            let cached_at = chrono::Utc::now().to_rfc3339();
+            let _ = db::cache_replies(&mut conn, &post_uri, &replies, &cached_at);
+            Ok(replies)
+        }
+        Err(e) => db::cached_replies(&mut conn, &post_uri).map_err(|_| e),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, specta::Type)]
+pub struct HomeInfo {
+    handle: String,
+    display_name: String,
+    cached_post_count: i64,
+    likes_given_count: i64,
+    last_synced_at: Option<String>,
+}
+
+/// Aggregate dashboard stats for the signed-in account, read entirely from
+/// local state so the frontend doesn't need several separate calls.
+#[tauri::command]
+#[specta::specta]
+fn home_info(
+    did: &str,
+    state: tauri::State<SessionStore>,
+    db: tauri::State<DbConnection>,
+) -> Result<HomeInfo, String> {
+    let session = state.get(did)?;
+    let mut conn = db.0.lock().unwrap();
+    let cached_post_count = db::cached_post_count(&mut conn).map_err(|e| e.to_string())?;
+    let likes_given_count = db::count_likes_given(&mut conn, did).map_err(|e| e.to_string())?;
+    let last_synced_at = db::last_synced_at(&mut conn).map_err(|e| e.to_string())?;
+    let display_name = db::cached_author(&mut conn, did)
+        .map_err(|e| e.to_string())?
+        .map(|author| author.display_name)
+        .unwrap_or_else(|| session.handle.clone());
+
+    Ok(HomeInfo {
+        handle: session.handle,
+        display_name,
+        cached_post_count,
+        likes_given_count,
+        last_synced_at,
+    })
+}
+
+/// Collects every `#[tauri::command]` for `tauri-specta` so bindings stay in
+/// sync with the Rust signatures instead of being hand-copied on the frontend.
+fn specta_builder() -> tauri_specta::Builder {
+    tauri_specta::Builder::<tauri::Wry>::new().commands(tauri_specta::collect_commands![
+        get_timeline,
+        login,
+        create_post,
+        like_post,
+        get_post_detail,
+        get_post_replies,
+        home_info,
+        firehose::start_firehose,
+        firehose::stop_firehose
+    ])
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let specta_builder = specta_builder();
+
+    #[cfg(debug_assertions)]
+    specta_builder
+        .export(specta_typescript::Typescript::default(), "../src/bindings.ts")
+        .expect("failed to export typescript bindings");
+
     bluesky::main();
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![
-            get_timeline,
-            login,
-            create_post,
-            like_post,
-            get_post_detail,
-            get_post_replies
-        ])
+        .manage(SessionStore::new())
+        .manage(FirehoseHandle::default())
+        .setup(|app| {
+            let app_data_dir = app.path().app_data_dir()?;
+            app.manage(db::establish_connection(&app_data_dir));
+            Ok(())
+        })
+        .register_uri_scheme_protocol(media::SCHEME, media::handle)
+        .invoke_handler(specta_builder.invoke_handler())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,100 @@
+// Serves authenticated blob CDN fetches (avatars, post images) through a
+// custom `atblob://{did}/{cid}` URI scheme instead of passing raw URLs.
+use std::fs;
+use std::path::PathBuf;
+
+use tauri::http::{Request, Response, StatusCode};
+use tauri::{Manager, UriSchemeContext};
+
+use crate::session::SessionStore;
+
+pub const SCHEME: &str = "atblob";
+
+/// Handles an `atblob://{did}/{cid}` request: serves the cached copy if
+/// present, otherwise fetches the blob via `com.atproto.sync.getBlob` using
+/// that account's stored session and writes it to disk for next time.
+pub fn handle<R: tauri::Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    let app = ctx.app_handle();
+    let uri = request.uri();
+    let did = uri.host().unwrap_or_default().to_string();
+    let cid = uri.path().trim_start_matches('/').to_string();
+
+    match load_blob(app, &did, &cid) {
+        Ok((bytes, mime)) => Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", mime)
+            .body(bytes)
+            .unwrap(),
+        Err(_) => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Vec::new())
+            .unwrap(),
+    }
+}
+
+/// Rewrites a Bluesky CDN URL (e.g. `.../img/avatar/plain/{did}/{cid}@jpeg`)
+/// into an `atblob://{did}/{cid}` reference the frontend can load through
+/// [`handle`], extracting the blob CID from the URL's last path segment.
+pub fn blob_uri(did: &str, cdn_url: &str) -> Option<String> {
+    let last_segment = cdn_url.rsplit('/').next()?;
+    let cid = last_segment.split('@').next()?;
+    if cid.is_empty() {
+        return None;
+    }
+    Some(format!("{SCHEME}://{did}/{cid}"))
+}
+
+fn load_blob<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    did: &str,
+    cid: &str,
+) -> Result<(Vec<u8>, String), String> {
+    let cache_dir = blob_cache_dir(app)?.join(did);
+    fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+    let blob_path = cache_dir.join(cid);
+    let mime_path = cache_dir.join(format!("{cid}.mime"));
+
+    if let (Ok(bytes), Ok(mime)) = (fs::read(&blob_path), fs::read_to_string(&mime_path)) {
+        return Ok((bytes, mime));
+    }
+
+    let store = app.state::<SessionStore>();
+    let session = store.get(did)?;
+    let client = reqwest::blocking::Client::new();
+    let resp = client
+        .get(format!(
+            "{}/xrpc/com.atproto.sync.getBlob",
+            session.service
+        ))
+        .query(&[("did", did), ("cid", cid)])
+        .bearer_auth(&session.access_jwt)
+        .send()
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("getBlob failed: {}", resp.status()));
+    }
+
+    let mime = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    let bytes = resp.bytes().map_err(|e| e.to_string())?.to_vec();
+
+    fs::write(&blob_path, &bytes).map_err(|e| e.to_string())?;
+    fs::write(&mime_path, &mime).map_err(|e| e.to_string())?;
+
+    Ok((bytes, mime))
+}
+
+fn blob_cache_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_cache_dir()
+        .map(|dir| dir.join("blobs"))
+        .map_err(|e| e.to_string())
+}
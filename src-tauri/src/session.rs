@@ -0,0 +1,39 @@
+// Managed state holding per-account AT Protocol sessions, keyed by DID.
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub did: String,
+    pub handle: String,
+    pub service: String,
+    pub access_jwt: String,
+    pub refresh_jwt: String,
+}
+
+#[derive(Default)]
+pub struct SessionStore(pub Mutex<HashMap<String, Session>>);
+
+impl SessionStore {
+    pub fn new() -> Self {
+        SessionStore(Mutex::new(HashMap::new()))
+    }
+
+    pub fn insert(&self, session: Session) {
+        let mut sessions = self.0.lock().unwrap();
+        sessions.insert(session.did.clone(), session);
+    }
+
+    pub fn get(&self, did: &str) -> Result<Session, String> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(did)
+            .cloned()
+            .ok_or_else(|| format!("no session for {did}"))
+    }
+
+    pub fn update(&self, session: Session) {
+        self.insert(session);
+    }
+}